@@ -2,6 +2,9 @@
 //! git-blame-parser
 //!
 
+use std::collections::HashMap;
+use std::io::BufRead;
+
 /// The porcelain format parser error
 #[derive(Debug, Clone)]
 pub struct ParseError(String);
@@ -76,6 +79,433 @@ impl Blame {
     pub fn short_commit(&self) -> String {
         self.commit[..7.min(self.commit.len())].to_string()
     }
+
+    /// Renders this blame into a display string using `template`.
+    ///
+    /// The template is parsed on each call; to render many lines with the same
+    /// template, parse it once with [`Template::parse`] and reuse the
+    /// [`Template`].
+    pub fn format(&self, template: &str) -> Result<String, ParseError> {
+        Template::parse(template).map(|t| t.render(self))
+    }
+
+    /// Canonicalizes the author and committer identities using `mailmap`.
+    ///
+    /// When a match is found the `author`/`author_mail` and
+    /// `committer`/`committer_mail` fields are rewritten, keeping the
+    /// surrounding `<...>` bracket convention on the mail fields.
+    pub fn apply_mailmap(&mut self, mailmap: &Mailmap) {
+        apply_mailmap_identity(&mut self.author, &mut self.author_mail, mailmap);
+        apply_mailmap_identity(&mut self.committer, &mut self.committer_mail, mailmap);
+    }
+}
+
+/// A canonical identity produced by a [`Mailmap`] lookup.
+///
+/// `name` is absent when the mapping line gives no proper name, in which case
+/// only the email is rewritten; [`Mailmap::parse`] always sets `email`.
+#[derive(Debug, Clone, Default)]
+struct MailmapEntry {
+    name: Option<String>,
+    email: Option<String>,
+}
+
+/// A parsed `.mailmap`, used to canonicalize author/committer identities.
+///
+/// Supports the standard mailmap grammar:
+///
+/// ```text
+/// Proper Name <proper@email>
+/// <proper@email> <commit@email>
+/// Proper Name <proper@email> <commit@email>
+/// Proper Name <proper@email> Commit Name <commit@email>
+/// ```
+///
+/// Lookups are keyed by the commit email, and additionally by the
+/// `(commit name, commit email)` pair for the last, most specific form.
+#[derive(Debug, Default)]
+pub struct Mailmap {
+    by_email: HashMap<String, MailmapEntry>,
+    by_name_email: HashMap<(String, String), MailmapEntry>,
+}
+
+impl Mailmap {
+    /// Parses the contents of a `.mailmap` file.
+    ///
+    /// Blank lines and `#` comments are ignored; malformed lines are skipped.
+    pub fn parse(text: &str) -> Self {
+        let mut map = Mailmap::default();
+
+        for raw in text.lines() {
+            let line = match raw.split_once('#') {
+                Some((head, _)) => head.trim(),
+                None => raw.trim(),
+            };
+            if line.is_empty() {
+                continue;
+            }
+
+            let first_lt = match line.find('<') {
+                Some(i) => i,
+                None => continue,
+            };
+            let first_gt = match line[first_lt..].find('>') {
+                Some(i) => first_lt + i,
+                None => continue,
+            };
+            let email1 = line[first_lt + 1..first_gt].trim();
+            let name1 = line[..first_lt].trim();
+            let rest = &line[first_gt + 1..];
+
+            let proper_name = (!name1.is_empty()).then(|| name1.to_string());
+
+            if let Some(lt2) = rest.find('<') {
+                let gt2 = match rest[lt2..].find('>') {
+                    Some(i) => lt2 + i,
+                    None => continue,
+                };
+                // Email matching is case-insensitive, so keys are lower-cased.
+                let commit_email = rest[lt2 + 1..gt2].trim().to_lowercase();
+                let commit_name = rest[..lt2].trim();
+
+                let entry = MailmapEntry {
+                    name: proper_name,
+                    email: Some(email1.to_string()),
+                };
+                if commit_name.is_empty() {
+                    map.by_email.insert(commit_email, entry);
+                } else {
+                    map.by_name_email
+                        .insert((commit_name.to_string(), commit_email), entry);
+                }
+            } else {
+                // `Proper Name <proper@email>`: the single email is also the
+                // commit email.
+                let entry = MailmapEntry {
+                    name: proper_name,
+                    email: Some(email1.to_string()),
+                };
+                map.by_email.insert(email1.to_lowercase(), entry);
+            }
+        }
+
+        map
+    }
+
+    /// Looks up the canonical identity for a `(name, email)` commit identity,
+    /// preferring the more specific name+email mapping.
+    fn lookup(&self, name: &str, email: &str) -> Option<&MailmapEntry> {
+        // Email matching is case-insensitive (commit names are not).
+        let email = email.to_lowercase();
+        self.by_name_email
+            .get(&(name.to_string(), email.clone()))
+            .or_else(|| self.by_email.get(&email))
+    }
+}
+
+/// Rewrites a single `name`/`mail` identity pair in place using `mailmap`,
+/// preserving the `<...>` bracket convention on the mail field.
+fn apply_mailmap_identity(name: &mut String, mail: &mut String, mailmap: &Mailmap) {
+    let email = mail
+        .trim()
+        .trim_start_matches('<')
+        .trim_end_matches('>')
+        .to_string();
+
+    if let Some(entry) = mailmap.lookup(name, &email) {
+        if let Some(n) = &entry.name {
+            *name = n.clone();
+        }
+        if let Some(e) = &entry.email {
+            *mail = format!("<{e}>");
+        }
+    }
+}
+
+/// Applies a [`Mailmap`] to a slice of blames, canonicalizing every author and
+/// committer identity in place.
+pub fn apply_mailmap(blames: &mut [Blame], mailmap: &Mailmap) {
+    for blame in blames.iter_mut() {
+        blame.apply_mailmap(mailmap);
+    }
+}
+
+/// Text alignment for a template placeholder's optional width spec.
+#[derive(Debug, Clone, Copy)]
+enum Align {
+    Left,
+    Right,
+    Center,
+}
+
+/// The optional `:<spec>` part of a placeholder.
+///
+/// For most fields this is an alignment and width (e.g. `>4`); for `{commit}`
+/// a bare number abbreviates the hash to that many hex characters.
+#[derive(Debug, Clone, Copy, Default)]
+struct FieldSpec {
+    align: Option<Align>,
+    width: Option<usize>,
+}
+
+/// A placeholder field supported by [`Template`].
+#[derive(Debug, Clone, Copy)]
+enum Field {
+    Commit,
+    Author,
+    AuthorMail,
+    Timestamp,
+    Summary,
+    OrigLine,
+    FinalLine,
+    Content,
+}
+
+/// A token of a parsed [`Template`]: either literal text or a placeholder.
+#[derive(Debug, Clone)]
+enum Token {
+    Literal(String),
+    Field(Field, FieldSpec),
+}
+
+/// A compiled [`Blame`] display template.
+///
+/// Templates are parsed once into a sequence of literal/placeholder tokens so
+/// the same template can be rendered cheaply against many lines. Supported
+/// placeholders are `{commit}`, `{author}`, `{author_mail}`, `{timestamp}`,
+/// `{summary}`, `{orig_line}`, `{final_line}` and `{content}`, each with an
+/// optional `:<spec>`:
+///
+/// * `{commit:8}` — abbreviate the hash to 8 hex characters.
+/// * `{orig_line:>4}` — right-align within a width of 4 (`<`, `>`, `^`).
+#[derive(Debug, Clone)]
+pub struct Template {
+    tokens: Vec<Token>,
+}
+
+impl Template {
+    /// Parses `template`, returning an error for any unknown placeholder name.
+    pub fn parse(template: &str) -> Result<Template, ParseError> {
+        let mut tokens = Vec::new();
+        let mut literal = String::new();
+        let mut chars = template.chars().peekable();
+
+        while let Some(c) = chars.next() {
+            if c != '{' {
+                literal.push(c);
+                continue;
+            }
+
+            let mut body = String::new();
+            let mut closed = false;
+            for pc in chars.by_ref() {
+                if pc == '}' {
+                    closed = true;
+                    break;
+                }
+                body.push(pc);
+            }
+            if !closed {
+                return Err(ParseError(format!("unterminated placeholder: {{{body}")));
+            }
+
+            if !literal.is_empty() {
+                tokens.push(Token::Literal(std::mem::take(&mut literal)));
+            }
+
+            let (name, spec_str) = match body.split_once(':') {
+                Some((n, s)) => (n, Some(s)),
+                None => (body.as_str(), None),
+            };
+
+            let field = match name {
+                "commit" => Field::Commit,
+                "author" => Field::Author,
+                "author_mail" => Field::AuthorMail,
+                "timestamp" => Field::Timestamp,
+                "summary" => Field::Summary,
+                "orig_line" => Field::OrigLine,
+                "final_line" => Field::FinalLine,
+                "content" => Field::Content,
+                other => {
+                    return Err(ParseError(format!("unknown placeholder: {other}")));
+                }
+            };
+
+            let spec = match spec_str {
+                Some(s) => parse_field_spec(s)?,
+                None => FieldSpec::default(),
+            };
+
+            tokens.push(Token::Field(field, spec));
+        }
+
+        if !literal.is_empty() {
+            tokens.push(Token::Literal(literal));
+        }
+
+        Ok(Template { tokens })
+    }
+
+    /// Renders `blame` into a display string.
+    pub fn render(&self, blame: &Blame) -> String {
+        let mut out = String::new();
+        for token in &self.tokens {
+            match token {
+                Token::Literal(text) => out.push_str(text),
+                Token::Field(field, spec) => out.push_str(&render_field(blame, *field, *spec)),
+            }
+        }
+        out
+    }
+}
+
+/// Parses a placeholder spec like `8`, `>4` or `^10` into a [`FieldSpec`].
+fn parse_field_spec(spec: &str) -> Result<FieldSpec, ParseError> {
+    let spec = spec.trim();
+    if spec.is_empty() {
+        return Ok(FieldSpec::default());
+    }
+
+    let mut chars = spec.chars();
+    let first = chars.clone().next().unwrap();
+    let (align, rest) = match first {
+        '<' => (Some(Align::Left), chars.by_ref().skip(1).collect::<String>()),
+        '>' => (Some(Align::Right), chars.by_ref().skip(1).collect::<String>()),
+        '^' => (Some(Align::Center), chars.by_ref().skip(1).collect::<String>()),
+        _ => (None, spec.to_string()),
+    };
+
+    let width = if rest.is_empty() {
+        None
+    } else {
+        Some(
+            rest.parse::<usize>()
+                .map_err(|_| ParseError(format!("invalid width spec: {spec}")))?,
+        )
+    };
+
+    Ok(FieldSpec { align, width })
+}
+
+/// Renders a single field of `blame`, applying its [`FieldSpec`].
+fn render_field(blame: &Blame, field: Field, spec: FieldSpec) -> String {
+    let mut value = match field {
+        Field::Commit => match spec.width {
+            // A bare width abbreviates the hash; alignment still pads below.
+            Some(n) if spec.align.is_none() => {
+                blame.commit[..n.min(blame.commit.len())].to_string()
+            }
+            _ => blame.commit.clone(),
+        },
+        Field::Author => blame.author.clone(),
+        Field::AuthorMail => blame.author_mail.clone(),
+        Field::Timestamp => blame.author_time.to_string(),
+        Field::Summary => blame.summary.clone(),
+        Field::OrigLine => blame.original_line_no.to_string(),
+        Field::FinalLine => blame.final_line_no.to_string(),
+        Field::Content => blame.content.clone(),
+    };
+
+    if let (Some(align), Some(width)) = (spec.align, spec.width) {
+        value = pad(&value, align, width);
+    }
+
+    value
+}
+
+/// Pads `value` to `width` columns using `align`, leaving it untouched when it
+/// is already at least that wide.
+fn pad(value: &str, align: Align, width: usize) -> String {
+    let len = value.chars().count();
+    if len >= width {
+        return value.to_string();
+    }
+
+    let fill = width - len;
+    match align {
+        Align::Left => format!("{value}{}", " ".repeat(fill)),
+        Align::Right => format!("{}{value}", " ".repeat(fill)),
+        Align::Center => {
+            let left = fill / 2;
+            let right = fill - left;
+            format!("{}{value}{}", " ".repeat(left), " ".repeat(right))
+        }
+    }
+}
+
+/// Typed datetime accessors, available when the `chrono` feature is enabled.
+///
+/// `author_time`/`committer_time` are stored as raw UNIX seconds and
+/// `author_tz`/`committer_tz` as strings like `+0900`; these helpers combine
+/// the two into a [`chrono::DateTime`] carrying the commit's own offset so
+/// consumers do not have to re-derive wall-clock time.
+#[cfg(feature = "chrono")]
+impl Blame {
+    /// The author timestamp as a [`chrono::DateTime`] in the author's timezone.
+    pub fn author_datetime(&self) -> chrono::DateTime<chrono::FixedOffset> {
+        datetime_with_offset(self.author_time, &self.author_tz)
+    }
+
+    /// The committer timestamp as a [`chrono::DateTime`] in the committer's
+    /// timezone.
+    pub fn committer_datetime(&self) -> chrono::DateTime<chrono::FixedOffset> {
+        datetime_with_offset(self.committer_time, &self.committer_tz)
+    }
+
+    /// Renders the author timestamp with a strftime `fmt` pattern, in the
+    /// author's own timezone.
+    ///
+    /// This is the building block for aligned blame columns (à la delta)
+    /// without reimplementing the offset math. Use [`committer_format_time`]
+    /// for the committer timestamp.
+    ///
+    /// [`committer_format_time`]: Blame::committer_format_time
+    pub fn format_time(&self, fmt: &str) -> String {
+        self.author_datetime().format(fmt).to_string()
+    }
+
+    /// Renders the committer timestamp with a strftime `fmt` pattern, in the
+    /// committer's own timezone.
+    pub fn committer_format_time(&self, fmt: &str) -> String {
+        self.committer_datetime().format(fmt).to_string()
+    }
+}
+
+/// Parses a git timezone offset (`+HHMM` / `-HHMM`) into seconds east of UTC.
+///
+/// Missing or empty offsets are treated as `+0000`, which also covers the
+/// all-zero "Not Committed Yet" commit.
+#[cfg(feature = "chrono")]
+fn parse_tz_seconds(tz: &str) -> i32 {
+    let tz = tz.trim();
+    if tz.len() < 5 {
+        return 0;
+    }
+
+    let sign = match tz.as_bytes()[0] {
+        b'-' => -1,
+        _ => 1,
+    };
+    let hours: i32 = tz[1..3].parse().unwrap_or(0);
+    let minutes: i32 = tz[3..5].parse().unwrap_or(0);
+    sign * (hours * 3600 + minutes * 60)
+}
+
+/// Combines UNIX `seconds` with a git `tz` offset into a [`chrono::DateTime`].
+#[cfg(feature = "chrono")]
+fn datetime_with_offset(
+    seconds: u64,
+    tz: &str,
+) -> chrono::DateTime<chrono::FixedOffset> {
+    use chrono::TimeZone;
+
+    let offset = chrono::FixedOffset::east_opt(parse_tz_seconds(tz))
+        .unwrap_or_else(|| chrono::FixedOffset::east_opt(0).unwrap());
+    offset
+        .timestamp_opt(seconds as i64, 0)
+        .single()
+        .unwrap_or_else(|| offset.timestamp_opt(0, 0).unwrap())
 }
 
 /// Parses the porcelain format output corresponding to a single line to
@@ -166,6 +596,406 @@ pub fn parse(porcelain: &str) -> Result<Vec<Blame>, ParseError> {
     Ok(blames)
 }
 
+/// The commit metadata shared between every line that belongs to the same
+/// commit.
+///
+/// In the default `--porcelain` format these fields are emitted only the
+/// first time a commit is seen; later blocks for the same SHA carry just the
+/// header and the content line. [`parse_porcelain`] caches this struct keyed
+/// by SHA so the suppressed fields can be restored.
+#[derive(Debug, Default, Clone)]
+struct CommitMeta {
+    filename: String,
+    summary: String,
+
+    previous_commit: Option<String>,
+    previous_filepath: Option<String>,
+
+    boundary: bool,
+
+    author: String,
+    author_mail: String,
+    author_time: u64,
+    author_tz: String,
+
+    committer: String,
+    committer_mail: String,
+    committer_time: u64,
+    committer_tz: String,
+}
+
+impl CommitMeta {
+    /// Copies the cached metadata into `blame`, leaving the header fields
+    /// (commit / line numbers) and the content untouched.
+    fn apply(&self, blame: &mut Blame) {
+        blame.filename = self.filename.clone();
+        blame.summary = self.summary.clone();
+        blame.previous_commit = self.previous_commit.clone();
+        blame.previous_filepath = self.previous_filepath.clone();
+        blame.boundary = self.boundary;
+        blame.author = self.author.clone();
+        blame.author_mail = self.author_mail.clone();
+        blame.author_time = self.author_time;
+        blame.author_tz = self.author_tz.clone();
+        blame.committer = self.committer.clone();
+        blame.committer_mail = self.committer_mail.clone();
+        blame.committer_time = self.committer_time;
+        blame.committer_tz = self.committer_tz.clone();
+    }
+}
+
+impl From<&Blame> for CommitMeta {
+    fn from(blame: &Blame) -> Self {
+        CommitMeta {
+            filename: blame.filename.clone(),
+            summary: blame.summary.clone(),
+            previous_commit: blame.previous_commit.clone(),
+            previous_filepath: blame.previous_filepath.clone(),
+            boundary: blame.boundary,
+            author: blame.author.clone(),
+            author_mail: blame.author_mail.clone(),
+            author_time: blame.author_time,
+            author_tz: blame.author_tz.clone(),
+            committer: blame.committer.clone(),
+            committer_mail: blame.committer_mail.clone(),
+            committer_time: blame.committer_time,
+            committer_tz: blame.committer_tz.clone(),
+        }
+    }
+}
+
+/// Parses the output of `git blame --porcelain`.
+///
+/// Unlike [`parse`], which expects the `--line-porcelain` output where every
+/// block repeats the full commit information, this handles the default
+/// porcelain format: the commit details (`author`, `summary`, …) are emitted
+/// only the first time a commit appears and are suppressed on subsequent
+/// lines that reference the same SHA.
+///
+/// The header line may carry a fourth field — the number of lines in the group
+/// — as in `<sha> <orig_lineno> <final_lineno> [<num_lines_in_group>]`; it is
+/// informational here and does not change how a block is parsed, since every
+/// line still ends with its own TAB-prefixed content line.
+pub fn parse_porcelain(porcelain: &str) -> Result<Vec<Blame>, ParseError> {
+    let mut lines = porcelain.lines();
+    let mut blames = Vec::new();
+    let mut cache: HashMap<String, CommitMeta> = HashMap::new();
+
+    let mut blob: Vec<&str> = Vec::new();
+    while let Some(line) = lines.next() {
+        blob.push(line);
+
+        // end of one blame output.
+        if line.starts_with('\t') {
+            let mut blame = parse_one_blame(&blob)?;
+
+            // A suppressed block carries only the header and content line, so
+            // the metadata fields are still at their defaults: restore them
+            // from the cache. Detect this by the absence of a `summary`, which
+            // git always emits with the full metadata.
+            let has_meta = blob
+                .iter()
+                .any(|l| l.split_once(' ').map(|(k, _)| k == "summary").unwrap_or(false));
+            if has_meta {
+                cache.insert(blame.commit.clone(), CommitMeta::from(&blame));
+            } else if let Some(meta) = cache.get(&blame.commit) {
+                meta.apply(&mut blame);
+            }
+
+            blames.push(blame);
+            blob.clear();
+        }
+    }
+
+    Ok(blames)
+}
+
+/// Returns `true` when `line` is an incremental/porcelain group header, i.e.
+/// it begins with a 40-character hex SHA followed by a line number.
+fn is_group_header(line: &str) -> bool {
+    match line.split_once(' ') {
+        Some((sha, rest)) => {
+            sha.len() == 40
+                && sha.bytes().all(|b| b.is_ascii_hexdigit())
+                && rest
+                    .split_whitespace()
+                    .next()
+                    .map(|n| n.bytes().all(|b| b.is_ascii_digit()))
+                    .unwrap_or(false)
+        }
+        None => false,
+    }
+}
+
+/// Applies a single metadata line (as emitted by the incremental format) to
+/// `blame`. Shared by the incremental parser and its cache filling.
+fn apply_incremental_field(blame: &mut Blame, line: &str) {
+    match line.split_once(' ') {
+        Some(("filename", value)) => blame.filename = value.to_string(),
+        Some(("summary", value)) => blame.summary = value.to_string(),
+
+        Some(("author", value)) => blame.author = value.to_string(),
+        Some(("author-mail", value)) => blame.author_mail = value.to_string(),
+        Some(("author-time", value)) => blame.author_time = value.parse::<u64>().unwrap_or(0),
+        Some(("author-tz", value)) => blame.author_tz = value.to_string(),
+
+        Some(("committer", value)) => blame.committer = value.to_string(),
+        Some(("committer-mail", value)) => blame.committer_mail = value.to_string(),
+        Some(("committer-time", value)) => {
+            blame.committer_time = value.parse::<u64>().unwrap_or(0)
+        }
+        Some(("committer-tz", value)) => blame.committer_tz = value.to_string(),
+
+        Some(("previous", value)) => {
+            if let Some((commit, filepath)) = value.split_once(' ') {
+                blame.previous_commit = Some(commit.to_string());
+                blame.previous_filepath = Some(filepath.to_string());
+            }
+        }
+
+        None if line == "boundary" => blame.boundary = true,
+        _ => {}
+    }
+}
+
+/// A streaming parser for the output of `git blame --incremental`.
+///
+/// The incremental format is designed for progressive consumption of large
+/// files: each group begins with `<sha> <orig_lineno> <final_lineno>
+/// <num_lines>`, followed by the commit metadata headers — which, as in the
+/// porcelain format, are emitted only the first time a SHA is seen — and is
+/// terminated by the start of the next group header (there is NO TAB-prefixed
+/// content line). Suppressed metadata is restored from a cache of the commits
+/// whose metadata has already been read; a group whose commit has no metadata
+/// yet is yielded with those fields left at their defaults, and is not updated
+/// when the metadata appears later.
+///
+/// The parser implements [`Iterator`], yielding one [`Blame`] per final line
+/// as each group completes, so a tool can begin displaying early lines before
+/// `git` has finished writing its output.
+pub struct IncrementalParser<R: BufRead> {
+    reader: R,
+    cache: HashMap<String, CommitMeta>,
+    /// Lines belonging to the group currently being accumulated.
+    group: Vec<String>,
+    /// `Blame`s produced by the last completed group, drained before more
+    /// input is read.
+    pending: std::collections::VecDeque<Blame>,
+    done: bool,
+}
+
+impl<R: BufRead> IncrementalParser<R> {
+    /// Creates a parser that reads incremental blame output from `reader`.
+    pub fn new(reader: R) -> Self {
+        IncrementalParser {
+            reader,
+            cache: HashMap::new(),
+            group: Vec::new(),
+            pending: std::collections::VecDeque::new(),
+            done: false,
+        }
+    }
+
+    /// Turns the accumulated group into [`Blame`]s, updating the commit cache.
+    fn flush_group(&mut self) {
+        if self.group.is_empty() {
+            return;
+        }
+
+        let header: Vec<&str> = self.group[0].split_whitespace().collect();
+        let commit = header.get(0).copied().unwrap_or("").to_string();
+        let orig = header.get(1).and_then(|v| v.parse::<usize>().ok()).unwrap_or(0);
+        let final_no = header.get(2).and_then(|v| v.parse::<usize>().ok()).unwrap_or(0);
+        let num = header.get(3).and_then(|v| v.parse::<usize>().ok()).unwrap_or(1);
+
+        let mut base = Blame::default();
+        base.commit = commit.clone();
+
+        let has_meta = self.group.iter().skip(1).any(|l| l.starts_with("summary "));
+        let has_filename = self.group.iter().skip(1).any(|l| l.starts_with("filename "));
+        for line in self.group.iter().skip(1) {
+            apply_incremental_field(&mut base, line);
+        }
+
+        if has_meta {
+            self.cache.insert(commit.clone(), CommitMeta::from(&base));
+        } else if let Some(meta) = self.cache.get(&commit) {
+            // Restore the suppressed metadata from the cache, then re-apply the
+            // per-group `filename` if this group carried one — the group's own
+            // filename is authoritative so rename tracking survives.
+            let group_filename = base.filename.clone();
+            meta.apply(&mut base);
+            if has_filename {
+                base.filename = group_filename;
+            }
+        }
+
+        for i in 0..num.max(1) {
+            let blame = Blame {
+                commit: base.commit.clone(),
+                original_line_no: orig + i,
+                final_line_no: final_no + i,
+                filename: base.filename.clone(),
+                summary: base.summary.clone(),
+                content: String::new(),
+                previous_commit: base.previous_commit.clone(),
+                previous_filepath: base.previous_filepath.clone(),
+                boundary: base.boundary,
+                author: base.author.clone(),
+                author_mail: base.author_mail.clone(),
+                author_time: base.author_time,
+                author_tz: base.author_tz.clone(),
+                committer: base.committer.clone(),
+                committer_mail: base.committer_mail.clone(),
+                committer_time: base.committer_time,
+                committer_tz: base.committer_tz.clone(),
+            };
+            self.pending.push_back(blame);
+        }
+
+        self.group.clear();
+    }
+}
+
+impl<R: BufRead> Iterator for IncrementalParser<R> {
+    type Item = Result<Blame, ParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(blame) = self.pending.pop_front() {
+                return Some(Ok(blame));
+            }
+            if self.done {
+                return None;
+            }
+
+            let mut buf = String::new();
+            match self.reader.read_line(&mut buf) {
+                Ok(0) => {
+                    // EOF: emit the final group.
+                    self.done = true;
+                    self.flush_group();
+                }
+                Ok(_) => {
+                    let line = buf.trim_end_matches(['\n', '\r']).to_string();
+                    if is_group_header(&line) && !self.group.is_empty() {
+                        self.flush_group();
+                        self.group.push(line);
+                    } else {
+                        self.group.push(line);
+                    }
+                }
+                Err(e) => {
+                    self.done = true;
+                    return Some(Err(ParseError(e.to_string())));
+                }
+            }
+        }
+    }
+}
+
+/// Parses `git blame --incremental` output from a [`BufRead`] source, invoking
+/// `callback` with each [`Blame`] as its group completes.
+///
+/// This is a thin wrapper over [`IncrementalParser`] for callers that prefer a
+/// push-style API to driving the iterator themselves.
+pub fn parse_incremental<R, F>(reader: R, mut callback: F) -> Result<(), ParseError>
+where
+    R: BufRead,
+    F: FnMut(Blame),
+{
+    for blame in IncrementalParser::new(reader) {
+        callback(blame?);
+    }
+    Ok(())
+}
+
+/// How line numbers should be displayed when rendering grouped blame.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LineNumberMode {
+    /// Show the line number on every line.
+    Every,
+    /// Show the line number only on the first line of each block.
+    PerBlock,
+    /// Line numbers are on; an alias for [`LineNumberMode::Every`] kept for
+    /// callers that toggle the gutter with an on/off switch.
+    On,
+}
+
+/// A contiguous run of [`Blame`] lines that all share the same commit.
+///
+/// Produced by [`group_blocks`]; viewers use the run-length structure to tint
+/// alternating authorship blocks and to collapse repeated line numbers.
+#[derive(Debug)]
+pub struct BlameBlock {
+    pub commit: String,
+    pub lines: Vec<Blame>,
+    pub start_final_line: usize,
+    pub len: usize,
+}
+
+impl BlameBlock {
+    /// Returns, for each line in the block, the final line number to display
+    /// under `mode` (or `None` when it should be suppressed).
+    pub fn line_numbers(&self, mode: LineNumberMode) -> Vec<Option<usize>> {
+        self.lines
+            .iter()
+            .enumerate()
+            .map(|(i, blame)| match mode {
+                LineNumberMode::PerBlock if i > 0 => None,
+                _ => Some(blame.final_line_no),
+            })
+            .collect()
+    }
+}
+
+/// Collapses `blames` into contiguous blocks where adjacent lines share the
+/// same commit.
+///
+/// The blames are consumed and moved into the returned blocks, preserving
+/// their original order.
+pub fn group_blocks(blames: Vec<Blame>) -> Vec<BlameBlock> {
+    let mut blocks: Vec<BlameBlock> = Vec::new();
+
+    for blame in blames {
+        match blocks.last_mut() {
+            Some(block) if block.commit == blame.commit => {
+                block.lines.push(blame);
+                block.len += 1;
+            }
+            _ => blocks.push(BlameBlock {
+                commit: blame.commit.clone(),
+                start_final_line: blame.final_line_no,
+                len: 1,
+                lines: vec![blame],
+            }),
+        }
+    }
+
+    blocks
+}
+
+/// Assigns each block a palette index, giving every distinct commit a stable
+/// slot in insertion order and wrapping with modulo when there are more
+/// commits than palette entries.
+///
+/// The palette is only inspected for its length, so callers can use any
+/// element type; index the palette with the returned values to tint each
+/// block.
+pub fn assign_palette<C>(blocks: &[BlameBlock], palette: &[C]) -> Vec<usize> {
+    let len = palette.len().max(1);
+    let mut seen: HashMap<&str, usize> = HashMap::new();
+
+    blocks
+        .iter()
+        .map(|block| {
+            let next = seen.len();
+            let idx = *seen.entry(block.commit.as_str()).or_insert(next);
+            idx % len
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -248,6 +1078,169 @@ mod tests {
         assert_eq!(first.committer_mail, "<not.committed.yet>");
     }
 
+    #[test]
+    fn parse_porcelain_reuses_commit_metadata() {
+        let raw = "\
+1111111111111111111111111111111111111111 1 1 2
+author Alice
+author-mail <alice@example.com>
+author-time 1744981061
+author-tz +0900
+committer Alice
+committer-mail <alice@example.com>
+committer-time 1744981061
+committer-tz +0900
+summary first commit
+filename src/lib.rs
+\tline one
+1111111111111111111111111111111111111111 2 2
+filename src/lib.rs
+\tline two
+";
+
+        let blames = parse_porcelain(raw).unwrap();
+        assert_eq!(blames.len(), 2);
+
+        // The suppressed second block should inherit the cached metadata.
+        let second = &blames[1];
+        assert_eq!(second.commit, "1111111111111111111111111111111111111111");
+        assert_eq!(second.final_line_no, 2);
+        assert_eq!(second.author, "Alice");
+        assert_eq!(second.summary, "first commit");
+        assert_eq!(second.content, "line two");
+    }
+
+    #[test]
+    fn parse_incremental_streams_and_caches() {
+        let raw = "\
+1111111111111111111111111111111111111111 1 1 2
+author Alice
+author-mail <alice@example.com>
+author-time 1744981061
+author-tz +0900
+committer Alice
+committer-mail <alice@example.com>
+committer-time 1744981061
+committer-tz +0900
+summary first commit
+filename src/lib.rs
+1111111111111111111111111111111111111111 5 3 1
+filename src/lib.rs
+";
+
+        let mut collected = Vec::new();
+        parse_incremental(std::io::Cursor::new(raw), |b| collected.push(b)).unwrap();
+
+        // The group of 2 lines expands into 2 blames, plus the later single.
+        assert_eq!(collected.len(), 3);
+        assert_eq!(collected[0].final_line_no, 1);
+        assert_eq!(collected[1].final_line_no, 2);
+        assert_eq!(collected[1].original_line_no, 2);
+
+        // The suppressed third block inherits the cached author/summary.
+        assert_eq!(collected[2].final_line_no, 3);
+        assert_eq!(collected[2].author, "Alice");
+        assert_eq!(collected[2].summary, "first commit");
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn datetime_uses_commit_offset() {
+        let mut blame = Blame::default();
+        blame.author_time = 1744981061;
+        blame.author_tz = String::from("+0900");
+
+        blame.committer_time = 1744981061;
+        blame.committer_tz = String::from("+0000");
+
+        let dt = blame.author_datetime();
+        assert_eq!(dt.offset().local_minus_utc(), 9 * 3600);
+        assert_eq!(blame.format_time("%Y-%m-%d"), "2025-04-18");
+        // The committer helper renders in the committer's own timezone.
+        assert_eq!(blame.committer_format_time("%H:%M"), "12:57");
+
+        // Empty tz falls back to UTC.
+        blame.author_tz = String::new();
+        assert_eq!(blame.author_datetime().offset().local_minus_utc(), 0);
+    }
+
+    #[test]
+    fn mailmap_rewrites_identities() {
+        let mailmap = Mailmap::parse(
+            "# canonicalize\n\
+             Proper Name <proper@example.com> <commit@example.com>\n\
+             <new@example.com> <old@example.com>\n",
+        );
+
+        let mut blame = Blame::default();
+        blame.author = String::from("Commit Name");
+        blame.author_mail = String::from("<commit@example.com>");
+        blame.committer = String::from("Whoever");
+        // Differing address case should still hit the mapping.
+        blame.committer_mail = String::from("<Old@Example.com>");
+
+        blame.apply_mailmap(&mailmap);
+
+        assert_eq!(blame.author, "Proper Name");
+        assert_eq!(blame.author_mail, "<proper@example.com>");
+        // Email-only mapping keeps the original name.
+        assert_eq!(blame.committer, "Whoever");
+        assert_eq!(blame.committer_mail, "<new@example.com>");
+    }
+
+    #[test]
+    fn template_expands_placeholders_and_specs() {
+        let mut blame = Blame::default();
+        blame.commit = String::from("abcdefghijklmnopqrstuvwxyz1234567890abcd");
+        blame.author = String::from("Alice");
+        blame.original_line_no = 7;
+        blame.content = String::from("let x = 1;");
+
+        let tpl = Template::parse("{commit:8} {orig_line:>4} {author}| {content}").unwrap();
+        assert_eq!(tpl.render(&blame), "abcdefgh    7 Alice| let x = 1;");
+
+        // The convenience method parses on demand.
+        assert_eq!(blame.format("{author}").unwrap(), "Alice");
+    }
+
+    #[test]
+    fn template_rejects_unknown_placeholder() {
+        assert!(Template::parse("{nope}").is_err());
+    }
+
+    #[test]
+    fn group_blocks_and_palette() {
+        let commits = ["aaa", "aaa", "bbb", "aaa"];
+        let blames: Vec<Blame> = commits
+            .iter()
+            .enumerate()
+            .map(|(i, c)| {
+                let mut b = Blame::default();
+                b.commit = c.to_string();
+                b.final_line_no = i + 1;
+                b
+            })
+            .collect();
+
+        let blocks = group_blocks(blames);
+        assert_eq!(blocks.len(), 3);
+        assert_eq!(blocks[0].len, 2);
+        assert_eq!(blocks[0].start_final_line, 1);
+
+        // PerBlock suppresses numbers after the first line of a block.
+        let nums = blocks[0].line_numbers(LineNumberMode::PerBlock);
+        assert_eq!(nums, vec![Some(1), None]);
+        assert_eq!(
+            blocks[0].line_numbers(LineNumberMode::Every),
+            vec![Some(1), Some(2)]
+        );
+
+        // Distinct commits get stable, insertion-ordered, wrapping indices.
+        let palette = ["red", "green"];
+        let idx = assign_palette(&blocks, &palette);
+        assert_eq!(idx, vec![0, 1, 0]);
+    }
+
     #[test]
     fn test_shor_commit() {
         let mut blame = Blame::default();